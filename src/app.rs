@@ -0,0 +1,792 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::Result;
+use codee::string::FromToStringCodec;
+use futures_concurrency::future::TryJoin;
+use leptos::*;
+use leptos_meta::*;
+use leptos_router::*;
+use leptos_use::use_cookie;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Account {
+    id: String,
+    username: String,
+    display_name: String,
+    avatar: String,
+    acct: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct MediaAttachment {
+    url: String,
+    #[serde(rename = "type")]
+    kind: String,
+    description: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Status {
+    id: String,
+    in_reply_to_id: Option<String>,
+    account: Account,
+    content: String,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    media_attachments: Vec<MediaAttachment>,
+    #[serde(default)]
+    favourites_count: u64,
+    #[serde(default)]
+    reblogs_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Context {
+    #[serde(default)]
+    ancestors: Vec<Status>,
+    descendants: Vec<Status>,
+}
+
+/// A status plus everything needed to render it: the ancestor chain above
+/// it, and the full descendant reply tree keyed by `in_reply_to_id`. Needs
+/// `Serialize`/`Deserialize` to satisfy `create_resource`'s `Serializable`
+/// bound under the ssr/hydrate combination.
+#[derive(Serialize, Deserialize, Clone)]
+struct Conversation {
+    ancestors: Vec<Status>,
+    root: Status,
+    children: HashMap<String, Vec<Status>>,
+}
+
+#[derive(Params, PartialEq)]
+struct IdParams {
+    host: Option<String>,
+    id: Option<String>,
+}
+
+/// Mastodon access tokens keyed by instance host, persisted in a single
+/// cookie (like upub's `token` cookie, but per-host since a session may
+/// span several instances).
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct TokenStore(HashMap<String, String>);
+
+impl std::fmt::Display for TokenStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(&self.0).unwrap_or_default())
+    }
+}
+
+impl FromStr for TokenStore {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self(serde_json::from_str(s).unwrap_or_default()))
+    }
+}
+
+/// Cookie-backed token store, provided via context so `Home` and `Threads`
+/// can both read and update it.
+#[derive(Clone, Copy)]
+struct Auth {
+    tokens: Signal<Option<TokenStore>>,
+    set_tokens: WriteSignal<Option<TokenStore>>,
+}
+
+impl Auth {
+    fn token_for(&self, host: &str) -> Option<String> {
+        self.tokens
+            .with(|tokens| tokens.as_ref().and_then(|store| store.0.get(host).cloned()))
+    }
+
+    fn set_token_for(&self, host: String, token: String) {
+        self.set_tokens.update(|tokens| {
+            tokens
+                .get_or_insert_with(Default::default)
+                .0
+                .insert(host, token);
+        });
+    }
+}
+
+#[cfg(feature = "ssr")]
+async fn fetch_status_and_context(
+    host: &str,
+    id: &str,
+    token: Option<&str>,
+) -> Result<(Status, Context)> {
+    let client = reqwest::Client::new();
+
+    let mut status = client.get(format!("https://{host}/api/v1/statuses/{id}"));
+    let mut context = client.get(format!("https://{host}/api/v1/statuses/{id}/context"));
+
+    if let Some(token) = token {
+        status = status.bearer_auth(token);
+        context = context.bearer_auth(token);
+    }
+
+    let (status, context) = (status.send(), context.send()).try_join().await?;
+    let (status, context) = (status.json::<Status>(), context.json::<Context>())
+        .try_join()
+        .await?;
+
+    Ok((status, context))
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+async fn fetch_status_and_context(
+    host: &str,
+    id: &str,
+    token: Option<&str>,
+    signal: &web_sys::AbortSignal,
+) -> Result<(Status, Context)> {
+    use gloo_net::http;
+
+    let mut status = http::Request::get(&format!("https://{host}/api/v1/statuses/{id}"))
+        .abort_signal(Some(signal));
+    let mut context =
+        http::Request::get(&format!("https://{host}/api/v1/statuses/{id}/context"))
+            .abort_signal(Some(signal));
+
+    if let Some(token) = token {
+        status = status.header("Authorization", &format!("Bearer {token}"));
+        context = context.header("Authorization", &format!("Bearer {token}"));
+    }
+
+    let (status, context) = (status.send(), context.send()).try_join().await?;
+    let (status, context) = (status.json::<Status>(), context.json::<Context>())
+        .try_join()
+        .await?;
+
+    Ok((status, context))
+}
+
+/// Group descendants by the status they're replying to, so a tree can be
+/// rendered by looking up `children[&status.id]` at each depth.
+fn build_reply_tree(descendants: Vec<Status>) -> HashMap<String, Vec<Status>> {
+    let mut children: HashMap<String, Vec<Status>> = HashMap::new();
+
+    for status in descendants {
+        if let Some(parent_id) = status.in_reply_to_id.clone() {
+            children.entry(parent_id).or_default().push(status);
+        }
+    }
+
+    children
+}
+
+/// Collapse the tree back down to the root author's own reply chain, for
+/// the "just the self-thread" toggle.
+fn self_thread(root: &Status, children: &HashMap<String, Vec<Status>>) -> Vec<Status> {
+    let account_id = root.account.id.clone();
+    let mut thread = vec![root.clone()];
+
+    loop {
+        // SAFETY: we initialized `thread` with `root`, so we have at least one element.
+        let last = thread.last().unwrap();
+
+        let matched: Vec<Status> = children
+            .get(&last.id)
+            .into_iter()
+            .flatten()
+            .filter(|s| s.account.id == account_id)
+            .cloned()
+            .collect();
+
+        if matched.is_empty() {
+            break;
+        }
+
+        thread.extend(matched);
+    }
+
+    thread
+}
+
+#[cfg(feature = "ssr")]
+async fn fetch_conversation(host: &str, id: &str, token: Option<&str>) -> Result<Conversation> {
+    let (root, context) = fetch_status_and_context(host, id, token).await?;
+    Ok(Conversation {
+        ancestors: context.ancestors,
+        root,
+        children: build_reply_tree(context.descendants),
+    })
+}
+
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+async fn fetch_conversation(
+    host: &str,
+    id: &str,
+    token: Option<&str>,
+    signal: &web_sys::AbortSignal,
+) -> Result<Conversation> {
+    let (root, context) = fetch_status_and_context(host, id, token, signal).await?;
+    Ok(Conversation {
+        ancestors: context.ancestors,
+        root,
+        children: build_reply_tree(context.descendants),
+    })
+}
+
+/// Fresh `AbortController`/`AbortSignal` pair for a single in-flight fetch,
+/// mirroring the hackernews_js_fetch example's cancel-on-renavigate pattern.
+#[cfg(any(feature = "csr", feature = "hydrate"))]
+fn new_abort_signal() -> (web_sys::AbortController, web_sys::AbortSignal) {
+    let controller = web_sys::AbortController::new().unwrap();
+    let signal = controller.signal();
+    (controller, signal)
+}
+
+/// Normalizes instance host input the same way `Home`'s URL-parsing effect
+/// does, so a token saved from a full URL like `https://Mastodon.Social/`
+/// lands under the same key `Threads` looks it up with via
+/// `url::Url::host_str()` (a bare host, lowercased, no scheme or path).
+fn normalize_host(input: &str) -> String {
+    let input = input.trim();
+
+    match url::Url::parse(input).ok().and_then(|url| url.host_str().map(str::to_owned)) {
+        Some(host) => host,
+        None => input.to_lowercase(),
+    }
+}
+
+/// A bare numeric/alphanumeric segment, accepted as a status/object ID.
+/// Mastodon uses numeric snowflake IDs, but Pleroma/Akkoma/GoToSocial use
+/// FlakeIDs or UUIDs, so digits-only is too strict.
+fn looks_like_status_id(segment: &str) -> bool {
+    !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Pull a status ID out of the handful of permalink shapes used across the
+/// Fediverse: Mastodon's `/web/statuses/:id` and `/users/:user/statuses/:id`,
+/// Pleroma/Akkoma's `/notice/:id` and `/@user/:id`, and `/objects/:uuid`
+/// ActivityPub object links. Anything else falls back to "last segment looks
+/// like an ID", and is rejected entirely otherwise.
+fn extract_status_id(segments: &[&str]) -> Option<String> {
+    match segments {
+        [.., "statuses", id] | [.., "notice", id] | [.., "objects", id] => {
+            looks_like_status_id(id).then(|| (*id).to_owned())
+        }
+        [.., last] => looks_like_status_id(last).then(|| (*last).to_owned()),
+        [] => None,
+    }
+}
+
+#[component]
+fn Home() -> impl IntoView {
+    let (url, set_url) = create_signal(String::from(""));
+
+    create_effect(move |_| {
+        if let Ok(parsed) = url::Url::parse(&url.get()) {
+            if let (Some(host), Some(segments)) = (parsed.host_str(), parsed.path_segments()) {
+                let segments: Vec<&str> = segments.collect();
+
+                if let Some(id) = extract_status_id(&segments) {
+                    let navigate = leptos_router::use_navigate();
+                    navigate(&format!("/{host}/{id}"), Default::default());
+                }
+            }
+        }
+    });
+
+    let auth = use_context::<Auth>().expect("Auth context is provided by App");
+    let (settings_host, set_settings_host) = create_signal(String::from(""));
+    let (settings_token, set_settings_token) = create_signal(String::from(""));
+
+    view! {
+      <main class="container">
+        <div class="grid">
+          <div>
+            <input
+              type="url"
+              placeholder="Mastodon status URL"
+              aria-label="Mastodon status URL"
+              on:input=move |ev| {
+                set_url.set(event_target_value(&ev));
+              }
+              prop:value=url/>
+          </div>
+        </div>
+        <details>
+          <summary>"Sign in to an instance"</summary>
+          <div class="grid">
+            <input
+              type="text"
+              placeholder="mastodon.social"
+              aria-label="Instance host"
+              on:input=move |ev| set_settings_host.set(event_target_value(&ev))
+              prop:value=settings_host/>
+            <input
+              type="password"
+              placeholder="Access token"
+              aria-label="Access token"
+              on:input=move |ev| set_settings_token.set(event_target_value(&ev))
+              prop:value=settings_token/>
+            <button
+              on:click=move |_| {
+                auth.set_token_for(normalize_host(&settings_host.get()), settings_token.get());
+                set_settings_token.set(String::from(""));
+              }>
+              "Save"
+            </button>
+          </div>
+        </details>
+      </main>
+    }
+}
+
+/// Plain-text excerpt of a status' HTML `content`, used for the `<meta
+/// name="description">` tag so link previews show something readable
+/// instead of raw markup.
+fn excerpt(content: &str, max_chars: usize) -> String {
+    let mut text = String::with_capacity(content.len());
+    let mut in_tag = false;
+
+    for c in content.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let text: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if text.chars().count() > max_chars {
+        let mut truncated: String = text.chars().take(max_chars).collect();
+        truncated.push('\u{2026}');
+        truncated
+    } else {
+        text
+    }
+}
+
+/// Human-readable relative timestamp ("3h ago") computed from an RFC 3339
+/// `created_at`, falling back to the raw string if it doesn't parse.
+fn relative_time(created_at: &str) -> String {
+    let Ok(created_at) = chrono::DateTime::parse_from_rfc3339(created_at) else {
+        return created_at.to_string();
+    };
+
+    let seconds = chrono::Utc::now()
+        .signed_duration_since(created_at)
+        .num_seconds()
+        .max(0);
+
+    match seconds {
+        0..=59 => format!("{seconds}s ago"),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+#[component]
+fn StatusCard(status: Status) -> impl IntoView {
+    let relative = relative_time(&status.created_at);
+
+    view! {
+      <article class="status-card">
+        <header>
+          <img class="avatar" src=status.account.avatar.clone() alt=""/>
+          <div>
+            <strong>{status.account.display_name.clone()}</strong>
+            " "
+            <span class="acct">{format!("@{}", status.account.acct)}</span>
+            " "
+            <time datetime=status.created_at.clone()>{relative}</time>
+          </div>
+        </header>
+        <div class="content" inner_html=status.content.clone()/>
+        {
+            status.media_attachments.iter().map(|media| {
+                let alt = media.description.clone().unwrap_or_default();
+                view! {
+                  <figure>
+                  {
+                      match media.kind.as_str() {
+                          "video" | "gifv" => view! { <video src=media.url.clone() controls=true/> }.into_view(),
+                          _ => view! { <img src=media.url.clone() alt=alt/> }.into_view(),
+                      }
+                  }
+                  {media.description.clone().map(|description| view! { <figcaption>{description}</figcaption> })}
+                  </figure>
+                }
+            }).collect_view()
+        }
+        <footer>
+          <span>{format!("{} favourites", status.favourites_count)}</span>
+          " "
+          <span>{format!("{} boosts", status.reblogs_count)}</span>
+        </footer>
+      </article>
+    }
+}
+
+/// Render a status and its replies recursively, indenting each depth so the
+/// branching structure of the conversation is visible.
+fn render_reply_tree(
+    status: Status,
+    children: &HashMap<String, Vec<Status>>,
+    depth: usize,
+) -> View {
+    let replies = children
+        .get(&status.id)
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| render_reply_tree(child, children, depth + 1))
+        .collect_view();
+
+    view! {
+      <div style=format!("margin-left: {}rem", depth as f32 * 1.5)>
+        <StatusCard status=status/>
+        <hr/>
+        {replies}
+      </div>
+    }
+    .into_view()
+}
+
+#[component]
+fn Threads() -> impl IntoView {
+    let params = use_params::<IdParams>();
+
+    let host = move || {
+        params.with(|params| {
+            params
+                .as_ref()
+                .map(|params| params.host.clone())
+                .unwrap_or_else(|_| Some(String::from("mastodon.social")))
+        })
+    };
+
+    let id = move || {
+        params.with(|params| {
+            params
+                .as_ref()
+                .map(|params| params.id.clone())
+                .unwrap_or_else(|_| Some(String::from("1")))
+        })
+    };
+
+    let auth = use_context::<Auth>().expect("Auth context is provided by App");
+    let token = move || host().and_then(|host| auth.token_for(&host));
+
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    let (abort_controller, set_abort_controller) = create_signal(None::<web_sys::AbortController>);
+
+    #[cfg(any(feature = "csr", feature = "hydrate"))]
+    on_cleanup(move || {
+        if let Some(controller) = abort_controller.get_untracked() {
+            controller.abort();
+        }
+    });
+
+    let thread = create_resource(
+        move || (host(), id(), token()),
+        move |(host, id, token)| {
+            #[cfg(any(feature = "csr", feature = "hydrate"))]
+            if let Some(controller) = abort_controller.get_untracked() {
+                controller.abort();
+            }
+
+            async move {
+                let host = host.unwrap();
+                let id = id.unwrap();
+
+                #[cfg(any(feature = "csr", feature = "hydrate"))]
+                {
+                    let (controller, signal) = new_abort_signal();
+                    set_abort_controller.set(Some(controller));
+                    fetch_conversation(&host, &id, token.as_deref(), &signal)
+                        .await
+                        .map_err(|err| err.to_string())
+                }
+
+                #[cfg(feature = "ssr")]
+                fetch_conversation(&host, &id, token.as_deref())
+                    .await
+                    .map_err(|err| err.to_string())
+            }
+        },
+    );
+
+    // Default to the full conversation tree, like upub/Mastodon timelines;
+    // the toggle below restricts the view to the root author's self-thread.
+    let (full_conversation, set_full_conversation) = create_signal(true);
+
+    view! {
+      // `thread` is read only inside this `<Suspense>`, and the route above is
+      // `ssr=SsrMode::Async`, so the server holds the response (including `<head>`)
+      // until the resource resolves, rather than flushing an empty `<Title>`/`<Meta>`
+      // and streaming the status in afterwards the way `OutOfOrder` would: a chat
+      // app's link unfurler never runs the hydration script that fills those in.
+      <Suspense fallback=|| view! { <p>"loading..."</p> }>
+        {move || thread.get().map(|result| match result {
+            Ok(conversation) => {
+                let name = conversation.root.account.display_name.clone();
+                let excerpt = excerpt(&conversation.root.content, 200);
+
+                let ancestors = conversation.ancestors.iter().map(|status| view! {
+                    <StatusCard status=status.clone()/><hr/>
+                }).collect_view();
+
+                let body = move || if full_conversation.get() {
+                    render_reply_tree(conversation.root.clone(), &conversation.children, 0)
+                } else {
+                    self_thread(&conversation.root, &conversation.children)
+                        .into_iter()
+                        .map(|status| view! { <StatusCard status=status/><hr/> })
+                        .collect_view()
+                };
+
+                view! {
+                  <Title text=name.clone()/>
+                  <Meta name="description" content=excerpt.clone()/>
+                  <Meta property="og:title" content=name/>
+                  <Meta property="og:description" content=excerpt/>
+                  <main class="container">
+                    <div class="grid">
+                      <div/>
+                      <div>
+                        <p>
+                          {move || if token().is_some() {
+                              "Viewing as an authenticated user \u{2014} private and follower-only threads may load."
+                          } else {
+                              "Viewing anonymously \u{2014} add an access token for this instance on the home page to see private threads."
+                          }}
+                        </p>
+                        <label>
+                          <input
+                            type="checkbox"
+                            prop:checked=full_conversation
+                            on:input=move |ev| set_full_conversation.set(event_target_checked(&ev))/>
+                          " show full conversation"
+                        </label>
+                        <div>{ancestors}{body}</div>
+                      </div>
+                      <div/>
+                    </div>
+                  </main>
+                }.into_view()
+            }
+            Err(err) => {
+                let reason = if token().is_some() {
+                    "the access token for this instance may be invalid or lack permission"
+                } else {
+                    "it may be private or follower-only \u{2014} add an access token for this instance on the home page"
+                };
+
+                view! {
+                  <Title text="merezhka"/>
+                  <main class="container">
+                    <div class="grid">
+                      <div/>
+                      <div>
+                        <p>{format!("couldn't load this thread ({err}); {reason}.")}</p>
+                      </div>
+                      <div/>
+                    </div>
+                  </main>
+                }.into_view()
+            }
+        })}
+      </Suspense>
+    }
+}
+
+#[component]
+pub fn App() -> impl IntoView {
+    provide_meta_context();
+
+    let (tokens, set_tokens) = use_cookie::<TokenStore, FromToStringCodec>("merezhka_tokens");
+    provide_context(Auth { tokens, set_tokens });
+
+    view! {
+      <Html lang="en"/>
+      <Title text="merezhka"/>
+      <Router>
+        <Routes>
+          <Route path="/" view=Home/>
+          // `Async`, not the default `OutOfOrder`: the whole point of this route is the
+          // server-rendered OpenGraph tags in `Threads`' `meta`, and a chat-app link
+          // unfurler never runs the hydration script that would fill them in later, so
+          // the `<head>` can't be flushed until `thread` has actually resolved.
+          <Route path="/:host/:id" view=Threads ssr=SsrMode::Async/>
+        </Routes>
+      </Router>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(id: &str, in_reply_to_id: Option<&str>, account_id: &str) -> Status {
+        Status {
+            id: id.to_owned(),
+            in_reply_to_id: in_reply_to_id.map(str::to_owned),
+            account: Account {
+                id: account_id.to_owned(),
+                username: account_id.to_owned(),
+                display_name: account_id.to_owned(),
+                avatar: String::new(),
+                acct: account_id.to_owned(),
+            },
+            content: format!("status {id}"),
+            created_at: String::new(),
+            media_attachments: Vec::new(),
+            favourites_count: 0,
+            reblogs_count: 0,
+        }
+    }
+
+    #[test]
+    fn build_reply_tree_groups_descendants_by_parent_id() {
+        let descendants = vec![
+            status("2", Some("1"), "a"),
+            status("3", Some("1"), "b"),
+            status("4", Some("2"), "a"),
+        ];
+
+        let children = build_reply_tree(descendants);
+
+        assert_eq!(
+            children.get("1").map(|c| c.iter().map(|s| s.id.clone()).collect::<Vec<_>>()),
+            Some(vec![String::from("2"), String::from("3")])
+        );
+        assert_eq!(
+            children.get("2").map(|c| c.iter().map(|s| s.id.clone()).collect::<Vec<_>>()),
+            Some(vec![String::from("4")])
+        );
+        assert!(!children.contains_key("4"));
+    }
+
+    #[test]
+    fn self_thread_follows_only_the_root_authors_own_replies() {
+        let root = status("1", None, "a");
+        let descendants = vec![
+            status("2", Some("1"), "a"),
+            status("3", Some("1"), "b"),
+            status("4", Some("2"), "a"),
+        ];
+        let children = build_reply_tree(descendants);
+
+        let thread = self_thread(&root, &children);
+
+        assert_eq!(
+            thread.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["1", "2", "4"]
+        );
+    }
+
+    #[test]
+    fn self_thread_is_just_the_root_when_nobody_replies() {
+        let root = status("1", None, "a");
+        let children = HashMap::new();
+
+        let thread = self_thread(&root, &children);
+
+        assert_eq!(thread.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(), vec!["1"]);
+    }
+
+    #[test]
+    fn looks_like_status_id_accepts_numeric_and_flake_ids_rejects_empty() {
+        assert!(looks_like_status_id("110224538603657411"));
+        assert!(looks_like_status_id("01HN8Z3K2Q7VJXC9R5M4T6W8YD"));
+        assert!(!looks_like_status_id(""));
+        assert!(!looks_like_status_id("has spaces"));
+    }
+
+    #[test]
+    fn extract_status_id_prefers_the_segment_after_statuses_notice_or_objects() {
+        assert_eq!(
+            extract_status_id(&["@user", "statuses", "123"]),
+            Some(String::from("123"))
+        );
+        assert_eq!(extract_status_id(&["notice", "abc-123"]), Some(String::from("abc-123")));
+        assert_eq!(
+            extract_status_id(&["objects", "0a1b2c3d"]),
+            Some(String::from("0a1b2c3d"))
+        );
+    }
+
+    #[test]
+    fn extract_status_id_falls_back_to_the_last_segment() {
+        assert_eq!(extract_status_id(&["@user", "123"]), Some(String::from("123")));
+    }
+
+    #[test]
+    fn extract_status_id_rejects_a_last_segment_that_doesnt_look_like_an_id() {
+        assert_eq!(extract_status_id(&["@user"]), None);
+        assert_eq!(extract_status_id(&[]), None);
+    }
+
+    #[test]
+    fn relative_time_buckets_seconds_minutes_hours_and_days() {
+        let ago = |seconds: i64| (chrono::Utc::now() - chrono::Duration::seconds(seconds)).to_rfc3339();
+
+        assert!(relative_time(&ago(5)).ends_with("s ago"));
+        assert!(relative_time(&ago(5 * 60)).ends_with("m ago"));
+        assert!(relative_time(&ago(5 * 3600)).ends_with("h ago"));
+        assert!(relative_time(&ago(5 * 86400)).ends_with("d ago"));
+    }
+
+    #[test]
+    fn relative_time_falls_back_to_the_raw_string_when_unparseable() {
+        assert_eq!(relative_time("not a timestamp"), "not a timestamp");
+    }
+
+    #[test]
+    fn excerpt_strips_tag_spans_not_just_angle_brackets() {
+        let html = r#"<p>hello <a href="https://example.com">world</a></p>"#;
+        assert_eq!(excerpt(html, 200), "hello world");
+    }
+
+    #[test]
+    fn excerpt_truncates_at_char_boundary_and_appends_ellipsis() {
+        let got = excerpt("hello world", 5);
+        assert_eq!(got, "hello\u{2026}");
+    }
+
+    #[test]
+    fn excerpt_leaves_short_plain_text_untouched() {
+        assert_eq!(excerpt("hello", 200), "hello");
+    }
+
+    #[test]
+    fn normalize_host_strips_scheme_and_path_and_lowercases() {
+        assert_eq!(normalize_host("https://Mastodon.Social/"), "mastodon.social");
+        assert_eq!(normalize_host("http://Mastodon.Social/@user/123"), "mastodon.social");
+    }
+
+    #[test]
+    fn normalize_host_leaves_a_bare_host_as_is_but_lowercased() {
+        assert_eq!(normalize_host("mastodon.social"), "mastodon.social");
+        assert_eq!(normalize_host("Mastodon.Social"), "mastodon.social");
+    }
+
+    #[test]
+    fn token_store_round_trips_through_its_display_fromstr_cookie_encoding() {
+        let mut store = TokenStore::default();
+        store.0.insert(String::from("mastodon.social"), String::from("token-a"));
+        store.0.insert(String::from("example.social"), String::from("token-b"));
+
+        let roundtripped: TokenStore = store.to_string().parse().unwrap();
+
+        assert_eq!(roundtripped.0, store.0);
+    }
+
+    #[test]
+    fn token_store_from_str_defaults_on_garbage_input_instead_of_erroring() {
+        let store: TokenStore = "not json".parse().unwrap();
+        assert_eq!(store.0, HashMap::new());
+    }
+}
@@ -0,0 +1,45 @@
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use leptos::LeptosOptions;
+use tower::ServiceExt;
+use tower_http::services::ServeDir;
+
+use crate::app::App;
+
+/// Serves a static asset from `site_root` if one matches the request path,
+/// otherwise falls through to rendering the app. Only requests that match
+/// none of the app's registered routes (`/`, `/:host/:id`) reach this handler.
+pub async fn file_and_error_handler(
+    uri: Uri,
+    State(options): State<LeptosOptions>,
+    req: Request<Body>,
+) -> Response {
+    let root = options.site_root.clone();
+
+    match get_static_file(uri, &root).await {
+        Ok(res) if res.status() == StatusCode::OK => res,
+        _ => leptos_axum::render_app_to_stream(options, App)(req)
+            .await
+            .into_response(),
+    }
+}
+
+async fn get_static_file(uri: Uri, root: &str) -> Result<Response, (StatusCode, String)> {
+    let req = Request::builder()
+        .uri(uri)
+        .body(Body::empty())
+        .expect("building a GET request from a URI cannot fail");
+
+    ServeDir::new(root)
+        .oneshot(req)
+        .await
+        .map(IntoResponse::into_response)
+        .map_err(|err| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed to serve static file: {err}"),
+            )
+        })
+}
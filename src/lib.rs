@@ -0,0 +1,21 @@
+#[cfg(not(any(feature = "ssr", feature = "csr", feature = "hydrate")))]
+compile_error!(
+    "merezhka must be built with one of the `ssr`, `csr`, or `hydrate` features enabled \
+     (e.g. `cargo build --no-default-features --features ssr`) \u{2014} the bare default \
+     feature set pulls in none of the fetch backends `app::fetch_status_and_context` needs."
+);
+
+pub mod app;
+
+#[cfg(feature = "ssr")]
+pub mod fileserv;
+
+#[cfg(feature = "hydrate")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn hydrate() {
+    use app::App;
+
+    console_error_panic_hook::set_once();
+    wasm_logger::init(wasm_logger::Config::default());
+    leptos::mount_to_body(App);
+}